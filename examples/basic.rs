@@ -1,9 +1,11 @@
-use diffable::{DiffableOperation, Graph, GraphBuilder, Node, Tensor};
+use diffable::{
+    optim::{Optimizer, Sgd},
+    DiffableOperation, Graph, GraphBuilder, Node, Tensor,
+};
 
 fn main() {
     let mut graph = network();
-
-    let lr = 0.01;
+    let mut optimiser = Sgd::new(0.01, 0.0, 0.0);
 
     graph.store_weights("a", &Float::from(1.0));
     graph.store_weights("b", &Float::from(0.0));
@@ -24,10 +26,7 @@ fn main() {
             graph.zero_grads();
             graph.backward();
 
-            for id in graph.weight_ids() {
-                let weight = graph.get_weights_mut(&id);
-                weight.val -= lr * weight.grad.unwrap();
-            }
+            optimiser.step(&mut graph);
         }
 
         println!("Loss: {batch_loss}")
@@ -88,6 +87,14 @@ impl Tensor for Float {
         Some(self.val)
     }
 
+    fn set_scalar(&mut self, value: f32) {
+        self.val = value;
+    }
+
+    fn get_grad_scalar(&self) -> Option<f32> {
+        self.grad
+    }
+
     fn copy_values_into(&self, dest: &mut Self) {
         dest.val = self.val;
     }
@@ -98,8 +105,43 @@ impl Tensor for Float {
         }
     }
 
-    fn set_grad_to_unit(&mut self) {
-        *self.grad.as_mut().unwrap() = 1.0;
+    fn seed_grad(&mut self, value: f32) {
+        *self.grad.as_mut().unwrap() = value;
+    }
+
+    fn grad(&self) -> Self {
+        Float {
+            val: self.grad.unwrap_or(0.0),
+            grad: None,
+        }
+    }
+
+    fn scale(&mut self, alpha: f32) {
+        self.val *= alpha;
+    }
+
+    fn scaled_add(&mut self, alpha: f32, other: &Self) {
+        self.val += alpha * other.val;
+    }
+
+    fn scaled_add_squared(&mut self, alpha: f32, other: &Self) {
+        self.val += alpha * other.val * other.val;
+    }
+
+    fn adam_update(&mut self, lr: f32, m: &Self, bias_m: f32, v: &Self, bias_v: f32, epsilon: f32) {
+        let m_hat = m.val / bias_m;
+        let v_hat = v.val / bias_v;
+        self.val -= lr * m_hat / (v_hat.sqrt() + epsilon);
+    }
+
+    #[cfg(feature = "serde")]
+    fn serialize_values(&self) -> Vec<f32> {
+        vec![self.val]
+    }
+
+    #[cfg(feature = "serde")]
+    fn deserialize_values(&mut self, values: &[f32]) {
+        self.val = values[0];
     }
 }
 
@@ -5,14 +5,27 @@ use crate::{
     Node, Tensor,
 };
 
+#[cfg(feature = "serde")]
+use std::io::{self, Read, Write};
+
+/// A weight's values as stored in a checkpoint, keyed by the human-readable
+/// name under which it was created with [`GraphBuilder::create_weights`](crate::GraphBuilder::create_weights).
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SerializedTensor {
+    values: Vec<f32>,
+}
+
 pub struct Graph<T: Tensor> {
     pub(crate) nodes: Vec<RefCell<T>>,
-    pub(crate) root: Node,
+    pub(crate) roots: Vec<Node>,
     pub(crate) inputs: HashMap<String, Node>,
     pub(crate) weights: HashMap<String, Node>,
     pub(crate) forward: OperationQueue<ForwardFunc<T>>,
     pub(crate) backward: OperationQueue<BackwardFunc<T>>,
     pub(crate) execution_context: T::ExecutionContext,
+    pub(crate) live_node_count: usize,
+    pub(crate) dead_ids: Vec<String>,
 }
 
 impl<T: Tensor> Display for Graph<T> {
@@ -22,14 +35,46 @@ impl<T: Tensor> Display for Graph<T> {
 }
 
 impl<T: Tensor> Graph<T> {
+    /// Runs the forward pass and returns the single output's scalar value.
+    ///
+    /// Panics if this graph has more than one output; use
+    /// [`Graph::forward_all`] for multi-output graphs.
     pub fn forward(&mut self) -> f32 {
+        assert_eq!(self.roots.len(), 1, "Graph has more than one output!");
+        self.forward_all()[0]
+    }
+
+    /// Runs the forward pass and returns every output's scalar value, in the
+    /// order the outputs were created.
+    pub fn forward_all(&mut self) -> Vec<f32> {
         self.forward
             .execute_on(&self.execution_context, &mut self.nodes);
-        self.nodes[self.root.0].borrow().get_scalar().unwrap()
+
+        self.roots
+            .iter()
+            .map(|root| self.nodes[root.0].borrow().get_scalar().unwrap())
+            .collect()
     }
 
+    /// Seeds every output's gradient to `1.0` and runs the backward pass.
     pub fn backward(&mut self) {
-        self.nodes[self.root.0].get_mut().set_grad_to_unit();
+        self.backward_from(&[]);
+    }
+
+    /// Seeds each listed output's gradient to the given scalar (any output
+    /// not listed defaults to `1.0`) and runs the backward pass, allowing
+    /// weighted combinations of gradients across multiple outputs to be
+    /// computed in a single sweep.
+    pub fn backward_from(&mut self, seeds: &[(Node, f32)]) {
+        for &root in &self.roots {
+            let seed = seeds
+                .iter()
+                .find(|(node, _)| *node == root)
+                .map_or(1.0, |(_, seed)| *seed);
+
+            self.nodes[root.0].get_mut().seed_grad(seed);
+        }
+
         self.backward
             .execute_on(&self.execution_context, &mut self.nodes);
     }
@@ -56,6 +101,23 @@ impl<T: Tensor> Graph<T> {
         self.weights.keys().cloned().collect()
     }
 
+    /// The number of nodes the outputs actually depend on, counting the
+    /// outputs themselves and every input, weight and intermediate node they
+    /// read from. Only the operation nodes among these are scheduled during
+    /// `forward`/`backward`. Any remaining nodes are dead subgraphs that were
+    /// pruned when the graph was built.
+    pub fn live_node_count(&self) -> usize {
+        self.live_node_count
+    }
+
+    /// Names of inputs/weights that are never read by any output, i.e. dead
+    /// subgraphs that were pruned when the graph was built. It's left to the
+    /// caller to decide whether and how to surface this (e.g. logging a
+    /// warning per name).
+    pub fn dead_ids(&self) -> &[String] {
+        &self.dead_ids
+    }
+
     pub fn get_input(&self, id: &str) -> std::cell::Ref<'_, T> {
         self.nodes[self.inputs[id].0].borrow()
     }
@@ -71,4 +133,118 @@ impl<T: Tensor> Graph<T> {
     pub fn get_weights_mut(&mut self, id: &str) -> &mut T {
         self.nodes[self.weights[id].0].get_mut()
     }
+
+    /// Validates a custom [`DiffableOperation`](crate::DiffableOperation)'s
+    /// backprop by comparing it against a central finite-difference estimate
+    /// of the gradient, for every weight whose value is exposed via
+    /// [`Tensor::get_scalar`].
+    ///
+    /// Works on multi-output graphs by reducing to the sum of all outputs,
+    /// matching [`Graph::backward`]'s default unit seeding of every output.
+    /// Perturbs each such weight by `+-eps`, rerunning [`Graph::forward_all`]
+    /// each time to estimate `(f(w+eps) - f(w-eps)) / (2*eps)` of that sum,
+    /// and compares it against the analytic gradient from one
+    /// `zero_grads`/`backward` pass. Returns the `(weight id, analytic,
+    /// numerical)` triples whose relative error exceeds `tol`.
+    pub fn check_gradients(&mut self, eps: f32, tol: f32) -> Result<(), Vec<(String, f32, f32)>> {
+        self.forward_all();
+        self.zero_grads();
+        self.backward();
+
+        let mut mismatches = Vec::new();
+
+        for id in self.weight_ids() {
+            let node = self.weights[&id];
+
+            let Some(original) = self.nodes[node.0].borrow().get_scalar() else {
+                continue;
+            };
+
+            let analytic = self.nodes[node.0].borrow().get_grad_scalar().unwrap();
+
+            self.nodes[node.0].get_mut().set_scalar(original + eps);
+            let loss_plus: f32 = self.forward_all().iter().sum();
+
+            self.nodes[node.0].get_mut().set_scalar(original - eps);
+            let loss_minus: f32 = self.forward_all().iter().sum();
+
+            self.nodes[node.0].get_mut().set_scalar(original);
+
+            let numerical = (loss_plus - loss_minus) / (2.0 * eps);
+            let relative_error = (analytic - numerical).abs() / numerical.abs().max(1e-8);
+
+            if relative_error > tol {
+                mismatches.push((id, analytic, numerical));
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
+    /// Serializes every weight, keyed by its name, to `w`.
+    #[cfg(feature = "serde")]
+    pub fn save_weights<W: Write>(&self, w: W) -> io::Result<()> {
+        let checkpoint = self
+            .weights
+            .iter()
+            .map(|(id, node)| {
+                let values = self.nodes[node.0].borrow().serialize_values();
+                (id.clone(), SerializedTensor { values })
+            })
+            .collect::<HashMap<_, _>>();
+
+        serde_json::to_writer(w, &checkpoint).map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
+    /// Restores every weight from a checkpoint previously written by
+    /// [`Graph::save_weights`], matching entries by name.
+    ///
+    /// Errors if the checkpoint is missing a weight, contains an unknown
+    /// weight, or a weight's stored length doesn't match the shape already
+    /// allocated for it in this graph.
+    #[cfg(feature = "serde")]
+    pub fn load_weights<R: Read>(&mut self, r: R) -> io::Result<()> {
+        let mut checkpoint: HashMap<String, SerializedTensor> =
+            serde_json::from_reader(r).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+
+        for id in self.weights.keys() {
+            if !checkpoint.contains_key(id) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Checkpoint is missing weight '{id}'!"),
+                ));
+            }
+        }
+
+        for (id, node) in &self.weights {
+            let serialized = checkpoint.remove(id).unwrap();
+            let mut tensor = self.nodes[node.0].get_mut();
+            let expected = tensor.serialize_values().len();
+
+            if serialized.values.len() != expected {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Shape mismatch loading weight '{id}': expected {expected} values, found {}!",
+                        serialized.values.len()
+                    ),
+                ));
+            }
+
+            tensor.deserialize_values(&serialized.values);
+        }
+
+        if let Some(id) = checkpoint.keys().next() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Checkpoint contains unknown weight '{id}'!"),
+            ));
+        }
+
+        Ok(())
+    }
 }
@@ -10,6 +10,39 @@ use crate::{operation::OperationQueue, DiffableOperation, Graph, Tensor};
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct Node(pub(crate) usize);
 
+/// A word-packed bitvector, one bit per node index, used to track node
+/// reachability.
+#[derive(Clone, Debug)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    fn set(&mut self, idx: usize) {
+        self.words[idx / 64] |= 1 << (idx % 64);
+    }
+
+    fn get(&self, idx: usize) -> bool {
+        self.words[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    fn or_with(&mut self, other: &BitSet) {
+        for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+            *word |= other_word;
+        }
+    }
+
+    fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
 #[derive(Debug)]
 pub struct NodeData<T: Tensor> {
     own: Node,
@@ -116,43 +149,135 @@ impl<T: Tensor> GraphBuilder<T> {
         }
     }
 
-    fn build_forward(&self, nodes: &[Node]) -> OperationQueue<T, false> {
+    /// Computes a post-order DFS over `parent_nodes` edges starting from every
+    /// node, so that a node is only scheduled once all of its parents have
+    /// already been scheduled. This is a valid forward evaluation order
+    /// regardless of the order in which nodes were created, and its reverse is
+    /// a valid backward order.
+    ///
+    /// Uses three-colour marking to detect back-edges (a node re-encountered
+    /// while still on the DFS stack), which indicate a cycle.
+    fn schedule(&self) -> Vec<Node> {
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        enum Mark {
+            Unvisited,
+            OnStack,
+            Done,
+        }
+
+        fn visit<T: Tensor>(
+            builder: &GraphBuilder<T>,
+            node: Node,
+            marks: &mut [Mark],
+            order: &mut Vec<Node>,
+        ) {
+            match marks[node.0] {
+                Mark::Done => return,
+                Mark::OnStack => panic!("Cycle detected in graph at node {}!", node.0),
+                Mark::Unvisited => {}
+            }
+
+            marks[node.0] = Mark::OnStack;
+
+            for &parent in &builder[node].parent_nodes {
+                visit(builder, parent, marks, order);
+            }
+
+            marks[node.0] = Mark::Done;
+            order.push(node);
+        }
+
+        let mut marks = vec![Mark::Unvisited; self.nodes.len()];
+        let mut order = Vec::with_capacity(self.nodes.len());
+
+        for idx in 0..self.nodes.len() {
+            visit(self, Node(idx), &mut marks, &mut order);
+        }
+
+        order
+    }
+
+    fn build_forward(&self, nodes: &[Node], live: &BitSet) -> OperationQueue<T, false> {
         let mut queue = OperationQueue::new();
 
         for &node in nodes {
             let data = &self[node];
 
             if let Some(operation) = data.parent_operation {
-                queue.push(operation, &data.parent_nodes, node);
+                if live.get(node.0) {
+                    queue.push(operation, &data.parent_nodes, node);
+                }
             }
         }
 
         queue
     }
 
-    fn build_backward(&self, nodes: &[Node]) -> OperationQueue<T, true> {
+    fn build_backward(&self, nodes: &[Node], live: &BitSet) -> OperationQueue<T, true> {
         let mut queue = OperationQueue::new();
 
         for &node in nodes.iter().rev() {
             let data = &self[node];
 
             if let Some(operation) = data.parent_operation {
-                queue.push(operation, &data.parent_nodes, node);
+                if live.get(node.0) {
+                    queue.push(operation, &data.parent_nodes, node);
+                }
             }
         }
 
         queue
     }
 
+    /// Computes, for every node, the set of nodes it transitively depends on
+    /// (its ancestors via `parent_nodes` edges), as a bitset. `schedule` must
+    /// be a topological order (parents before children), so each node's
+    /// ancestor set can be built by OR-combining its direct parents' own bits
+    /// with their already-computed ancestor sets in a single linear pass.
+    fn ancestor_bitsets(&self, schedule: &[Node]) -> Vec<BitSet> {
+        let mut ancestors = vec![BitSet::new(self.nodes.len()); self.nodes.len()];
+
+        for &node in schedule {
+            for &parent in &self[node].parent_nodes {
+                ancestors[node.0].set(parent.0);
+                let parent_ancestors = ancestors[parent.0].clone();
+                ancestors[node.0].or_with(&parent_ancestors);
+            }
+        }
+
+        ancestors
+    }
+
+    /// Computes the set of nodes `roots` actually depend on (themselves plus
+    /// all of their ancestors), so that dead subgraphs (like a branch whose
+    /// output is never read) can be skipped entirely during
+    /// `forward`/`backward`.
+    fn live_nodes(&self, schedule: &[Node], roots: &[Node]) -> BitSet {
+        let ancestors = self.ancestor_bitsets(schedule);
+
+        let mut live = BitSet::new(self.nodes.len());
+
+        for &root in roots {
+            live.set(root.0);
+            live.or_with(&ancestors[root.0]);
+        }
+
+        live
+    }
+
     pub fn build(&self, execution_context: T::ExecutionContext) -> Graph<T> {
-        assert_eq!(self.roots.len(), 1, "Graph must have a single output!");
+        assert!(!self.roots.is_empty(), "Graph must have at least one output!");
 
-        let root = *self.roots.iter().next().unwrap();
-        assert!(self[root].requires_grad, "Output cannot be an input!");
-        assert!(
-            !self.weights.contains(&root),
-            "Can't output trainable weights!"
-        );
+        let mut roots = self.roots.iter().copied().collect::<Vec<_>>();
+        roots.sort_by_key(|node| node.0);
+
+        for &root in &roots {
+            assert!(self[root].requires_grad, "Output cannot be an input!");
+            assert!(
+                !self.weights.contains(&root),
+                "Can't output trainable weights!"
+            );
+        }
 
         let nodes = self
             .nodes
@@ -172,23 +297,30 @@ impl<T: Tensor> GraphBuilder<T> {
             .map(|&node| (self[node].id.clone().unwrap(), node))
             .collect::<HashMap<_, _>>();
 
-        let node_ids = self
-            .nodes
+        let schedule = self.schedule();
+        let live = self.live_nodes(&schedule, &roots);
+
+        let mut dead_ids = inputs
             .iter()
-            .map(|node_data| node_data.own)
+            .chain(&weights)
+            .filter(|(_, &node)| !live.get(node.0))
+            .map(|(id, _)| id.clone())
             .collect::<Vec<_>>();
+        dead_ids.sort();
 
-        let forward = self.build_forward(&node_ids);
-        let backward = self.build_backward(&node_ids);
+        let forward = self.build_forward(&schedule, &live);
+        let backward = self.build_backward(&schedule, &live);
 
         Graph {
             nodes,
-            root,
+            roots,
             inputs,
             weights,
             forward,
             backward,
             execution_context,
+            live_node_count: live.count_ones(),
+            dead_ids,
         }
     }
 }
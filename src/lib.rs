@@ -1,9 +1,12 @@
 mod graph;
 pub mod graph_builder;
 mod operation;
+pub mod optim;
 mod tensor;
 
 pub use graph::Graph;
+#[cfg(feature = "serde")]
+pub use graph::SerializedTensor;
 pub use graph_builder::{GraphBuilder, Node};
 pub use operation::DiffableOperation;
 pub use tensor::Tensor;
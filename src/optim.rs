@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use crate::{Graph, Tensor};
+
+/// Generically updates a [`Graph`]'s weights from their computed gradients
+/// after a `backward` pass, without needing to know the concrete [`Tensor`]
+/// implementation's fields.
+pub trait Optimizer<T: Tensor> {
+    fn step(&mut self, graph: &mut Graph<T>);
+}
+
+/// Stochastic gradient descent, with optional momentum and (decoupled)
+/// weight decay.
+#[derive(Debug)]
+pub struct Sgd<T: Tensor> {
+    pub lr: f32,
+    pub momentum: f32,
+    pub weight_decay: f32,
+    velocity: HashMap<String, T>,
+}
+
+impl<T: Tensor> Sgd<T> {
+    pub fn new(lr: f32, momentum: f32, weight_decay: f32) -> Self {
+        Self {
+            lr,
+            momentum,
+            weight_decay,
+            velocity: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Tensor> Optimizer<T> for Sgd<T> {
+    fn step(&mut self, graph: &mut Graph<T>) {
+        for id in graph.weight_ids() {
+            let grad = graph.get_weights(&id).grad();
+
+            if self.momentum != 0.0 {
+                let velocity = self.velocity.entry(id.clone()).or_insert_with(T::default);
+                velocity.scale(self.momentum);
+                velocity.scaled_add(1.0, &grad);
+                graph.get_weights_mut(&id).scaled_add(-self.lr, &*velocity);
+            } else {
+                graph.get_weights_mut(&id).scaled_add(-self.lr, &grad);
+            }
+
+            // Decoupled weight decay (SGDW): shrinks the weight directly,
+            // independently of the gradient and momentum buffer above.
+            if self.weight_decay != 0.0 {
+                graph
+                    .get_weights_mut(&id)
+                    .scale(1.0 - self.lr * self.weight_decay);
+            }
+        }
+    }
+}
+
+/// Adam, maintaining a first- and second-moment buffer per weight.
+#[derive(Debug)]
+pub struct Adam<T: Tensor> {
+    pub lr: f32,
+    pub beta1: f32,
+    pub beta2: f32,
+    pub epsilon: f32,
+    timestep: i32,
+    m: HashMap<String, T>,
+    v: HashMap<String, T>,
+}
+
+impl<T: Tensor> Adam<T> {
+    pub fn new(lr: f32) -> Self {
+        Self {
+            lr,
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+            timestep: 0,
+            m: HashMap::new(),
+            v: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Tensor> Optimizer<T> for Adam<T> {
+    fn step(&mut self, graph: &mut Graph<T>) {
+        self.timestep += 1;
+        let bias1 = 1.0 - self.beta1.powi(self.timestep);
+        let bias2 = 1.0 - self.beta2.powi(self.timestep);
+
+        for id in graph.weight_ids() {
+            let grad = graph.get_weights(&id).grad();
+
+            let m = self.m.entry(id.clone()).or_insert_with(T::default);
+            m.scale(self.beta1);
+            m.scaled_add(1.0 - self.beta1, &grad);
+
+            let v = self.v.entry(id.clone()).or_insert_with(T::default);
+            v.scale(self.beta2);
+            v.scaled_add_squared(1.0 - self.beta2, &grad);
+
+            graph
+                .get_weights_mut(&id)
+                .adam_update(self.lr, &*m, bias1, &*v, bias2, self.epsilon);
+        }
+    }
+}
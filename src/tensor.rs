@@ -13,9 +13,55 @@ pub trait Tensor: Debug + Default {
 
     fn get_scalar(&self) -> Option<f32>;
 
+    /// Overwrites this tensor's value with `value`, for tensors exposed via
+    /// [`Tensor::get_scalar`]. Used by
+    /// [`Graph::check_gradients`](crate::Graph::check_gradients) to perturb a
+    /// weight for a finite-difference check.
+    fn set_scalar(&mut self, value: f32);
+
+    /// Returns this tensor's gradient as a scalar, for tensors exposed via
+    /// [`Tensor::get_scalar`]. Used by
+    /// [`Graph::check_gradients`](crate::Graph::check_gradients).
+    fn get_grad_scalar(&self) -> Option<f32>;
+
     fn copy_values_into(&self, dest: &mut Self);
 
     fn zero_grad(&mut self);
 
-    fn set_grad_to_unit(&mut self);
+    /// Seeds this tensor's gradient to `value`, e.g. `1.0` for a normal
+    /// scalar output. Used to start a backward pass from this node.
+    fn seed_grad(&mut self, value: f32);
+
+    /// Returns a tensor holding this tensor's current gradient as its value.
+    /// Used by the [`optim`](crate::optim) module to read a weight's
+    /// gradient generically.
+    fn grad(&self) -> Self;
+
+    /// Scales this tensor's value in place by `alpha`.
+    fn scale(&mut self, alpha: f32);
+
+    /// Adds `alpha * other` into this tensor's value in place, e.g.
+    /// `weight.scaled_add(-lr, &grad)` for an SGD step.
+    fn scaled_add(&mut self, alpha: f32, other: &Self);
+
+    /// Adds `alpha * other^2` (elementwise) into this tensor's value in
+    /// place. Used to maintain an Adam-style second-moment buffer without a
+    /// generic elementwise multiply.
+    fn scaled_add_squared(&mut self, alpha: f32, other: &Self);
+
+    /// Applies one bias-corrected Adam update to this tensor's value in
+    /// place: `self -= lr * (m / bias_m) / (sqrt(v / bias_v) + epsilon)`.
+    #[allow(clippy::too_many_arguments)]
+    fn adam_update(&mut self, lr: f32, m: &Self, bias_m: f32, v: &Self, bias_v: f32, epsilon: f32);
+
+    /// Flattens this tensor's values into a checkpoint-friendly buffer, for use
+    /// with [`Graph::save_weights`](crate::Graph::save_weights).
+    #[cfg(feature = "serde")]
+    fn serialize_values(&self) -> Vec<f32>;
+
+    /// Restores this tensor's values from a buffer previously produced by
+    /// [`Tensor::serialize_values`], for use with
+    /// [`Graph::load_weights`](crate::Graph::load_weights).
+    #[cfg(feature = "serde")]
+    fn deserialize_values(&mut self, values: &[f32]);
 }